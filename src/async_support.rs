@@ -0,0 +1,121 @@
+//! Async pulling, enabled via the `async` feature.
+//!
+//! Where [`try_pull`](crate::try_pull) returns `None` and [`pull`](crate::pull) falls back to
+//! allocating a fresh object, [`pull_async`] instead awaits an object becoming available,
+//! registering the task's [`Waker`] with the pool and retrying when a [`Reusable`] is returned.
+
+use crate::{try_pull, Pool, Reusable};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::task::{Context, Poll, Waker};
+
+impl<T> Pool<T> {
+    /// Registers `waker` and returns a key that can later be passed to
+    /// [`Pool::remove_waiter`] to cancel the registration, so a dropped `PullFuture` doesn't
+    /// leave a stale waiter behind for `wake_one` to pop and no-op on.
+    pub(crate) fn register_waiter(&self, waker: Waker) -> u64 {
+        let key = self.waiter_seq.fetch_add(1, Ordering::Relaxed);
+        self.waiters.lock().push_back((key, waker));
+        key
+    }
+
+    pub(crate) fn remove_waiter(&self, key: u64) {
+        self.waiters.lock().retain(|(k, _)| *k != key);
+    }
+
+    pub(crate) fn wake_one(&self) {
+        if let Some((_, waker)) = self.waiters.lock().pop_front() {
+            waker.wake();
+        }
+    }
+
+    /// A [`futures_core::Stream`] of [`Reusable<T>`] objects, each awaited in turn until an
+    /// object is available in the pool.
+    pub fn stream(self: &Arc<Self>) -> PoolStream<T> {
+        PoolStream {
+            pool: self.clone(),
+            pending: None,
+        }
+    }
+}
+
+/// Awaits an available object, bounding concurrency to the pool's real capacity instead of
+/// allocating past it the way [`pull`](crate::pull)'s fallback does.
+pub async fn pull_async<T>(pool: Arc<Pool<T>>) -> Reusable<T> {
+    PullFuture {
+        pool,
+        waiter_key: None,
+    }
+    .await
+}
+
+/// The [`Future`] returned by [`pull_async`].
+pub struct PullFuture<T> {
+    pool: Arc<Pool<T>>,
+    // The key of this future's currently-registered waiter, if any. Used by `Drop` to deregister
+    // it so a future cancelled (e.g. by a `select!` timeout) while pending doesn't leave a
+    // dangling waker in the pool's queue for `wake_one` to pop and no-op on.
+    waiter_key: Option<u64>,
+}
+
+impl<T> Future for PullFuture<T> {
+    type Output = Reusable<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+
+        if let Some(reusable) = try_pull(this.pool.clone()) {
+            return Poll::Ready(reusable);
+        }
+
+        // Drop any registration from an earlier poll before making a new one, so only the
+        // current waiter stays queued and `Drop` can deregister it.
+        if let Some(key) = this.waiter_key.take() {
+            this.pool.remove_waiter(key);
+        }
+        this.waiter_key = Some(this.pool.register_waiter(cx.waker().clone()));
+
+        // An object may have been attached between the check above and registering the waker;
+        // check again so that return doesn't race a wakeup that already happened.
+        match try_pull(this.pool.clone()) {
+            Some(reusable) => Poll::Ready(reusable),
+            None => Poll::Pending,
+        }
+    }
+}
+
+impl<T> Drop for PullFuture<T> {
+    fn drop(&mut self) {
+        if let Some(key) = self.waiter_key {
+            self.pool.remove_waiter(key);
+        }
+    }
+}
+
+/// A [`futures_core::Stream`] of [`Reusable<T>`] objects produced by [`Pool::stream`].
+pub struct PoolStream<T> {
+    pool: Arc<Pool<T>>,
+    pending: Option<PullFuture<T>>,
+}
+
+impl<T> futures_core::Stream for PoolStream<T> {
+    type Item = Reusable<T>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let pool = self.pool.clone();
+        let fut = self.pending.get_or_insert(PullFuture {
+            pool,
+            waiter_key: None,
+        });
+
+        match Pin::new(fut).poll(cx) {
+            Poll::Ready(reusable) => {
+                self.pending = None;
+                Poll::Ready(Some(reusable))
+            }
+            Poll::Pending => Poll::Pending,
+        }
+    }
+}
@@ -46,69 +46,408 @@
 //!
 //! # Warning
 //!
-//! Objects in the pool are not automatically reset, they are returned but NOT reset
+//! Objects created with [`Pool::new`] are not automatically reset, they are returned but NOT reset
 //! You may want to call `object.reset()` or  `object.clear()`
 //! or any other equivalent for the object that you are using, after pulling from the pool
 //!
+//! If you'd rather have this happen for you, use [`Pool::with_reset`] to supply a closure that is
+//! run on every object right before it is returned to the pool, so whatever you `pull` is always
+//! ready to use
+//!
+//! ## Sharding for Concurrent Workloads
+//!
+//! A single `Pool` keeps every object behind one lock, which can become a bottleneck when many
+//! threads `pull`/`attach` at once. [`Pool::new_sharded`] splits the pool into several
+//! independent free lists instead; each thread is pinned to a "home" shard, so the common case
+//! never contends with other threads, and a shard only scans its siblings when its own list runs
+//! dry
+//!
+//! ## Lock-Free Shards
+//!
+//! Every constructor above backs its shards with a `parking_lot::Mutex<Vec<T>>`. The
+//! `*_lockfree` family (e.g. [`Pool::new_lockfree`]) instead backs each shard with a
+//! [`treiber::TreiberStack`], a lock-free Treiber stack reclaimed via `crossbeam_epoch`, so
+//! taking an object from or returning one to a shard takes no lock, at the cost of an allocation
+//! per stored object.
+//!
+//! ## Async Pulls
+//!
+//! Behind the `async` feature, `pull_async` awaits an available object instead of falling back
+//! to allocating a fresh one when the pool is saturated, so callers can bound their concurrency
+//! to the pool's real capacity.
+//!
+//! ## Bounded Pools
+//!
+//! `pull`'s fallback allocates past `cap` under load, and by default everything it allocates is
+//! kept once it's returned. [`Pool::new_bounded`] caps that steady-state growth: once `max`
+//! objects are held, further returns drop the object instead of growing the pool, so a burst of
+//! transient overflow allocations doesn't permanently raise the pool's memory high-water mark.
+//!
+//! ## Observability
+//!
+//! Every `try_pull` that finds the pool saturated is counted; read it back with
+//! [`Pool::fail_count`], [`Pool::last_fail`], or all at once via [`Pool::stats`] to detect an
+//! undersized pool in production.
+//!
 //! [`std::sync::Arc`]: https://doc.rust-lang.org/stable/std/sync/struct.Arc.html
 
+mod treiber;
+
+#[cfg(feature = "async")]
+mod async_support;
+#[cfg(feature = "async")]
+pub use async_support::{pull_async, PoolStream, PullFuture};
+
 use parking_lot::Mutex;
+use std::cell::Cell;
 use std::mem::{forget, ManuallyDrop};
 use std::ops::{Deref, DerefMut};
-use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
+use treiber::TreiberStack;
 
 pub type Stack<T> = Vec<T>;
 
+/// A single shard's free list. Implemented by the default `parking_lot::Mutex`-guarded stack and
+/// by the lock-free [`TreiberStack`]; `Pool<T>` only ever talks to shards through this trait.
+trait ShardBackend<T>: Send + Sync {
+    fn push(&self, t: T);
+    fn pop(&self) -> Option<T>;
+    fn len(&self) -> usize;
+}
+
+impl<T: Send> ShardBackend<T> for Mutex<Stack<T>> {
+    #[inline]
+    fn push(&self, t: T) {
+        self.lock().push(t)
+    }
+
+    #[inline]
+    fn pop(&self) -> Option<T> {
+        self.lock().pop()
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        self.lock().len()
+    }
+}
+
+impl<T: Send + Sync> ShardBackend<T> for TreiberStack<T> {
+    #[inline]
+    fn push(&self, t: T) {
+        TreiberStack::push(self, t)
+    }
+
+    #[inline]
+    fn pop(&self) -> Option<T> {
+        TreiberStack::pop(self)
+    }
+
+    #[inline]
+    fn len(&self) -> usize {
+        TreiberStack::len(self)
+    }
+}
+
+thread_local! {
+    // A stable, cheap-to-read per-thread number used to pick each thread's home shard.
+    // Assigned once per thread on first use and cached for the life of the thread.
+    static SHARD_HINT: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+static NEXT_SHARD_HINT: AtomicUsize = AtomicUsize::new(0);
+
+fn shard_hint() -> usize {
+    SHARD_HINT.with(|hint| {
+        if let Some(h) = hint.get() {
+            h
+        } else {
+            let h = NEXT_SHARD_HINT.fetch_add(1, Ordering::Relaxed);
+            hint.set(Some(h));
+            h
+        }
+    })
+}
+
 pub struct Pool<T> {
-    objects: Mutex<Stack<T>>,
+    shards: Vec<Box<dyn ShardBackend<T>>>,
+    reset: Box<dyn Fn(&mut T) + Send + Sync>,
+    max_capacity: Option<usize>,
+    // Tracks the number of objects currently held across all shards; only kept up to date (and
+    // only consulted) when `max_capacity` is `Some`, so admission under the cap can be decided
+    // atomically instead of via a separate `len()` check that races `attach`'s push.
+    stored: AtomicUsize,
+    capacity: usize,
+    // Each waiter is keyed by a sequence number so a cancelled `PullFuture` can remove its own
+    // entry instead of leaving a dangling `Waker` for `wake_one` to pop and no-op on.
+    #[cfg(feature = "async")]
+    waiters: Mutex<std::collections::VecDeque<(u64, std::task::Waker)>>,
+    #[cfg(feature = "async")]
+    waiter_seq: std::sync::atomic::AtomicU64,
     pub name: String,
-    pub last_fail: Mutex<Instant>,
-    pub cnt_fail: AtomicUsize,
+    last_fail: Mutex<Instant>,
+    cnt_fail: AtomicUsize,
 }
 
-impl<T> Pool<T> {
+impl<T: Send + Sync + 'static> Pool<T> {
     #[inline]
     pub fn new<F>(name: String, cap: usize, init: F) -> Pool<T>
     where
         F: Fn() -> T,
     {
-        let mut objects = Stack::new();
+        Pool::with_reset(name, cap, init, |_| {})
+    }
 
-        for _ in 0..cap {
-            objects.push(init());
-        }
+    /// Like [`Pool::new`], but `reset` is called on every object just before it is
+    /// returned to the pool, so objects pulled out are always ready to use without
+    /// requiring callers to remember to clean them up themselves.
+    #[inline]
+    pub fn with_reset<F, G>(name: String, cap: usize, init: F, reset: G) -> Pool<T>
+    where
+        F: Fn() -> T,
+        G: Fn(&mut T) + Send + Sync + 'static,
+    {
+        Pool::with_reset_sharded(name, cap, 1, init, reset)
+    }
+
+    /// Like [`Pool::new`], but splits `cap` objects across `shards` independent free lists to
+    /// avoid a single shared lock becoming a contention point under many threads. Passing `0`
+    /// for `shards` defaults to the number of available CPUs.
+    #[inline]
+    pub fn new_sharded<F>(name: String, cap: usize, shards: usize, init: F) -> Pool<T>
+    where
+        F: Fn() -> T,
+    {
+        Pool::with_reset_sharded(name, cap, shards, init, |_| {})
+    }
+
+    /// Combines [`Pool::new_sharded`] and [`Pool::with_reset`].
+    pub fn with_reset_sharded<F, G>(
+        name: String,
+        cap: usize,
+        shards: usize,
+        init: F,
+        reset: G,
+    ) -> Pool<T>
+    where
+        F: Fn() -> T,
+        G: Fn(&mut T) + Send + Sync + 'static,
+    {
+        Pool::build(name, cap, shards, None, init, reset, |objects| {
+            Box::new(Mutex::new(objects))
+        })
+    }
+
+    /// Like [`Pool::new`], but once `max` objects are held, any further [`attach`](Pool::attach)
+    /// (including the automatic one on [`Reusable`] drop) drops the object instead of growing the
+    /// pool past `max`. Lets transient overflow allocations happen under load without permanently
+    /// raising the pool's steady-state memory high-water mark.
+    #[inline]
+    pub fn new_bounded<F>(name: String, cap: usize, max: usize, init: F) -> Pool<T>
+    where
+        F: Fn() -> T,
+    {
+        Pool::with_reset_bounded(name, cap, max, init, |_| {})
+    }
+
+    /// Combines [`Pool::new_bounded`] and [`Pool::with_reset`].
+    pub fn with_reset_bounded<F, G>(
+        name: String,
+        cap: usize,
+        max: usize,
+        init: F,
+        reset: G,
+    ) -> Pool<T>
+    where
+        F: Fn() -> T,
+        G: Fn(&mut T) + Send + Sync + 'static,
+    {
+        Pool::build(name, cap, 1, Some(max), init, reset, |objects| {
+            Box::new(Mutex::new(objects))
+        })
+    }
+
+    /// Like [`Pool::new`], but backs each shard with a lock-free [`treiber::TreiberStack`]
+    /// instead of a `parking_lot::Mutex`, so popping from or pushing onto a shard never takes a
+    /// lock.
+    #[inline]
+    pub fn new_lockfree<F>(name: String, cap: usize, init: F) -> Pool<T>
+    where
+        F: Fn() -> T,
+    {
+        Pool::with_reset_lockfree(name, cap, init, |_| {})
+    }
+
+    /// Combines [`Pool::new_lockfree`] and [`Pool::with_reset`].
+    pub fn with_reset_lockfree<F, G>(name: String, cap: usize, init: F, reset: G) -> Pool<T>
+    where
+        F: Fn() -> T,
+        G: Fn(&mut T) + Send + Sync + 'static,
+    {
+        Pool::build(name, cap, 1, None, init, reset, |objects| {
+            let stack = TreiberStack::new();
+            for object in objects {
+                stack.push(object);
+            }
+            Box::new(stack)
+        })
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn build<F, G>(
+        name: String,
+        cap: usize,
+        shards: usize,
+        max_capacity: Option<usize>,
+        init: F,
+        reset: G,
+        make_shard: impl Fn(Stack<T>) -> Box<dyn ShardBackend<T>>,
+    ) -> Pool<T>
+    where
+        F: Fn() -> T,
+        G: Fn(&mut T) + Send + Sync + 'static,
+    {
+        let shards = if shards == 0 {
+            std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1)
+        } else {
+            shards
+        };
+
+        let shards = (0..shards)
+            .map(|i| {
+                // spread `cap` as evenly as possible across the shards
+                let shard_cap = cap / shards + usize::from(i < cap % shards);
+                let mut objects = Stack::new();
+                for _ in 0..shard_cap {
+                    objects.push(init());
+                }
+                make_shard(objects)
+            })
+            .collect();
 
         Pool {
-            objects: Mutex::new(objects),
+            shards,
+            reset: Box::new(reset),
+            max_capacity,
+            stored: AtomicUsize::new(cap),
+            capacity: cap,
+            #[cfg(feature = "async")]
+            waiters: Mutex::new(std::collections::VecDeque::new()),
+            #[cfg(feature = "async")]
+            waiter_seq: std::sync::atomic::AtomicU64::new(0),
             name,
             last_fail: Mutex::new(Instant::now()),
             cnt_fail: AtomicUsize::new(0),
         }
     }
+}
+
+impl<T> Pool<T> {
+    #[inline]
+    fn home_shard(&self) -> usize {
+        shard_hint() % self.shards.len()
+    }
 
     #[inline]
     pub fn len(&self) -> usize {
-        self.objects.lock().len()
+        self.shards.iter().map(|shard| shard.len()).sum()
     }
 
     #[inline]
     pub fn is_empty(&self) -> bool {
-        self.objects.lock().is_empty()
+        self.len() == 0
     }
 
     #[inline]
     pub fn attach(&self, t: T) {
-        self.objects.lock().push(t)
+        if let Some(max) = self.max_capacity {
+            // Reserve a slot and admit `t` atomically, so concurrent attaches can't all
+            // observe room under `max` and overshoot it between the check and the push.
+            let admitted = self
+                .stored
+                .fetch_update(Ordering::AcqRel, Ordering::Acquire, |stored| {
+                    (stored < max).then_some(stored + 1)
+                })
+                .is_ok();
+
+            if !admitted {
+                // already at the cap; drop `t` instead of growing the pool past it
+                return;
+            }
+        }
+
+        self.shards[self.home_shard()].push(t);
+        #[cfg(feature = "async")]
+        self.wake_one();
+    }
+
+    fn record_fail(&self) {
+        self.cnt_fail.fetch_add(1, Ordering::Relaxed);
+        *self.last_fail.lock() = Instant::now();
     }
+
+    // Mirrors `attach`'s `stored` bookkeeping for a successful pop; a no-op for unbounded pools.
+    fn record_take(&self) {
+        if self.max_capacity.is_some() {
+            self.stored.fetch_sub(1, Ordering::AcqRel);
+        }
+    }
+
+    /// The number of times `try_pull` has found the pool saturated.
+    #[inline]
+    pub fn fail_count(&self) -> usize {
+        self.cnt_fail.load(Ordering::Relaxed)
+    }
+
+    /// When `try_pull` last found the pool saturated.
+    #[inline]
+    pub fn last_fail(&self) -> Instant {
+        *self.last_fail.lock()
+    }
+
+    /// A snapshot of the pool's health, useful for driving alerting or autoscaling off of an
+    /// undersized pool.
+    pub fn stats(&self) -> Stats {
+        Stats {
+            available: self.len(),
+            capacity: self.capacity,
+            fail_count: self.fail_count(),
+            last_fail: self.last_fail(),
+        }
+    }
+}
+
+/// A point-in-time snapshot returned by [`Pool::stats`].
+#[derive(Debug, Clone, Copy)]
+pub struct Stats {
+    pub available: usize,
+    pub capacity: usize,
+    pub fail_count: usize,
+    pub last_fail: Instant,
 }
 
 pub fn try_pull<T>(pool: Arc<Pool<T>>) -> Option<Reusable<T>> {
-    pool.objects
-        .lock()
-        .pop()
-        .map(|data| Reusable::new(Some(pool.clone()), data))
+    let home = pool.home_shard();
+
+    if let Some(data) = pool.shards[home].pop() {
+        pool.record_take();
+        return Some(Reusable::new(Some(pool.clone()), data));
+    }
+
+    // home shard is empty, steal from whichever other shard has something
+    for shard in pool.shards.iter() {
+        if let Some(data) = shard.pop() {
+            pool.record_take();
+            return Some(Reusable::new(Some(pool.clone()), data));
+        }
+    }
+
+    pool.record_fail();
+    None
 }
 
 pub fn pull<T, F: Fn() -> T>(pool: Arc<Pool<T>>, fallback: F) -> Reusable<T> {
@@ -163,7 +502,11 @@ impl<T> Drop for Reusable<T> {
     fn drop(&mut self) {
         if let Some(p) = self.pool.as_mut() {
             let pool = p.clone();
-            unsafe { pool.attach(self.take()) }
+            unsafe {
+                let mut data = self.take();
+                (pool.reset)(&mut data);
+                pool.attach(data)
+            }
         } else {
             unsafe {
                 ManuallyDrop::drop(&mut self.data);
@@ -212,6 +555,37 @@ mod tests {
         assert_eq!(pool.len(), 2);
     }
 
+    #[test]
+    fn reset_on_return() {
+        let pool = Arc::new(Pool::with_reset(
+            "test".to_string(),
+            1,
+            Vec::new,
+            |v: &mut Vec<i32>| v.clear(),
+        ));
+
+        let mut object = try_pull(pool.clone()).unwrap();
+        object.push(1);
+        drop(object);
+
+        assert!(try_pull(pool.clone()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn reset_not_called_when_detached() {
+        let pool = Arc::new(Pool::with_reset(
+            "test".to_string(),
+            1,
+            Vec::new,
+            |v: &mut Vec<i32>| v.clear(),
+        ));
+
+        let mut object = try_pull(pool.clone()).unwrap();
+        object.push(1);
+        let (_, object) = object.detach();
+        assert_eq!(object[0], 1);
+    }
+
     #[test]
     fn e2e() {
         let pool = Arc::new(Pool::new("test".to_string(), 10, || Vec::new()));
@@ -226,10 +600,190 @@ mod tests {
         assert!(try_pull(pool.clone()).is_none());
         drop(objects);
         assert!(try_pull(pool.clone()).is_some());
+    }
+
+    #[test]
+    fn sharded_len_sums_across_shards() {
+        let pool = Arc::new(Pool::new_sharded(
+            "test".to_string(),
+            10,
+            4,
+            Vec::<u8>::new,
+        ));
+        assert_eq!(pool.len(), 10);
 
-        for i in 10..0 {
-            let mut object = pool.objects.lock().pop().unwrap();
-            assert_eq!(object.pop(), Some(i));
+        let mut objects = Vec::new();
+        while let Some(object) = try_pull(pool.clone()) {
+            objects.push(object);
         }
+        assert_eq!(objects.len(), 10);
+        assert!(pool.is_empty());
+
+        drop(objects);
+        assert_eq!(pool.len(), 10);
+    }
+
+    #[test]
+    fn lockfree_pull_and_attach() {
+        let pool = Arc::new(Pool::new_lockfree("test".to_string(), 2, Vec::<u8>::new));
+        assert_eq!(pool.len(), 2);
+
+        let object1 = try_pull(pool.clone());
+        let object2 = try_pull(pool.clone());
+        let object3 = try_pull(pool.clone());
+
+        assert!(object1.is_some());
+        assert!(object2.is_some());
+        assert!(object3.is_none());
+
+        drop(object1);
+        drop(object2);
+        assert_eq!(pool.len(), 2);
+    }
+
+    // A waker that just records whether it was woken; good enough to drive a future by hand
+    // without pulling in an async runtime just for these tests.
+    #[cfg(feature = "async")]
+    fn test_waker(flag: Arc<std::sync::atomic::AtomicBool>) -> std::task::Waker {
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::task::{RawWaker, RawWakerVTable, Waker};
+
+        fn clone(ptr: *const ()) -> RawWaker {
+            unsafe { Arc::increment_strong_count(ptr as *const AtomicBool) };
+            RawWaker::new(ptr, &VTABLE)
+        }
+        fn wake(ptr: *const ()) {
+            let flag = unsafe { Arc::from_raw(ptr as *const AtomicBool) };
+            flag.store(true, Ordering::SeqCst);
+        }
+        fn wake_by_ref(ptr: *const ()) {
+            let flag = unsafe { &*(ptr as *const AtomicBool) };
+            flag.store(true, Ordering::SeqCst);
+        }
+        fn drop_fn(ptr: *const ()) {
+            unsafe { drop(Arc::from_raw(ptr as *const AtomicBool)) };
+        }
+        static VTABLE: RawWakerVTable = RawWakerVTable::new(clone, wake, wake_by_ref, drop_fn);
+
+        let ptr = Arc::into_raw(flag) as *const ();
+        unsafe { Waker::from_raw(RawWaker::new(ptr, &VTABLE)) }
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn async_pull_waits_for_attach() {
+        use crate::pull_async;
+        use std::future::Future;
+        use std::pin::Pin;
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::task::Context;
+
+        let pool = Arc::new(Pool::new("test".to_string(), 1, Vec::<u8>::new));
+        let held = try_pull(pool.clone()).unwrap();
+
+        let mut fut = Box::pin(pull_async(pool.clone()));
+        let woken = Arc::new(AtomicBool::new(false));
+        let waker = test_waker(woken.clone());
+        let mut cx = Context::from_waker(&waker);
+
+        assert!(!Pin::new(&mut fut).poll(&mut cx).is_ready());
+        assert!(!woken.load(Ordering::SeqCst));
+
+        drop(held);
+        assert!(woken.load(Ordering::SeqCst));
+        assert!(Pin::new(&mut fut).poll(&mut cx).is_ready());
+    }
+
+    // Regression test for a cancelled `pull_async` leaving a stale waiter behind: dropping a
+    // pending future (as a `select!` with a timeout would on the timeout branch) must deregister
+    // its waker, or a later `attach` can wake the dead entry via `wake_one`'s FIFO pop and leave a
+    // still-pending future asleep even though an object is available.
+    #[cfg(feature = "async")]
+    #[test]
+    fn dropping_pending_pull_async_does_not_strand_other_waiters() {
+        use crate::pull_async;
+        use std::future::Future;
+        use std::pin::Pin;
+        use std::sync::atomic::{AtomicBool, Ordering};
+        use std::task::Context;
+
+        let pool = Arc::new(Pool::new("test".to_string(), 1, Vec::<u8>::new));
+        let held = try_pull(pool.clone()).unwrap();
+
+        let mut first = Box::pin(pull_async(pool.clone()));
+        let first_woken = Arc::new(AtomicBool::new(false));
+        let first_waker = test_waker(first_woken.clone());
+        assert!(!Pin::new(&mut first)
+            .poll(&mut Context::from_waker(&first_waker))
+            .is_ready());
+
+        let mut second = Box::pin(pull_async(pool.clone()));
+        let second_woken = Arc::new(AtomicBool::new(false));
+        let second_waker = test_waker(second_woken.clone());
+        assert!(!Pin::new(&mut second)
+            .poll(&mut Context::from_waker(&second_waker))
+            .is_ready());
+
+        // Simulate the timeout branch of a `select!` winning: the first future is cancelled
+        // while still pending.
+        drop(first);
+
+        drop(held);
+        assert!(!first_woken.load(Ordering::SeqCst));
+        assert!(second_woken.load(Ordering::SeqCst));
+        assert!(Pin::new(&mut second)
+            .poll(&mut Context::from_waker(&second_waker))
+            .is_ready());
+    }
+
+    #[test]
+    fn bounded_pool_drops_past_max() {
+        let pool = Arc::new(Pool::new_bounded("test".to_string(), 0, 2, Vec::<u8>::new));
+
+        let object1 = pull(pool.clone(), Vec::new);
+        let object2 = pull(pool.clone(), Vec::new);
+        let object3 = pull(pool.clone(), Vec::new);
+
+        drop(object1);
+        drop(object2);
+        assert_eq!(pool.len(), 2);
+
+        drop(object3);
+        assert_eq!(pool.len(), 2);
+    }
+
+    #[test]
+    fn bounded_pool_rejects_concurrent_overshoot() {
+        let pool = Arc::new(Pool::new_bounded("test".to_string(), 0, 4, Vec::<u8>::new));
+
+        let handles: Vec<_> = (0..16)
+            .map(|_| {
+                let pool = pool.clone();
+                std::thread::spawn(move || pool.attach(Vec::new()))
+            })
+            .collect();
+
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        assert_eq!(pool.len(), 4);
+    }
+
+    #[test]
+    fn fail_counter_tracks_saturation() {
+        let pool = Arc::new(Pool::<Vec<u8>>::new("test".to_string(), 1, Vec::new));
+
+        assert_eq!(pool.fail_count(), 0);
+
+        let _object1 = try_pull(pool.clone());
+        assert!(try_pull(pool.clone()).is_none());
+
+        assert_eq!(pool.fail_count(), 1);
+        let stats = pool.stats();
+        assert_eq!(stats.available, 0);
+        assert_eq!(stats.capacity, 1);
+        assert_eq!(stats.fail_count, 1);
+        assert!(stats.last_fail.elapsed() < std::time::Duration::from_secs(5));
     }
 }
@@ -0,0 +1,116 @@
+//! A lock-free Treiber stack, used as an alternative backend to the `parking_lot::Mutex`-guarded
+//! free list for pools created with `Pool::new_lockfree` and friends
+//!
+//! Nodes are reclaimed with [`crossbeam_epoch`] so that a node unlinked by one thread's `pop`
+//! can't be freed out from under another thread that is still mid-CAS against it (the classic
+//! ABA hazard of a naive `AtomicPtr` stack).
+
+use crossbeam_epoch::{self as epoch, Atomic, Owned};
+use std::mem::ManuallyDrop;
+use std::ptr;
+use std::sync::atomic::Ordering;
+
+struct Node<T> {
+    data: ManuallyDrop<T>,
+    next: Atomic<Node<T>>,
+}
+
+pub struct TreiberStack<T> {
+    head: Atomic<Node<T>>,
+}
+
+impl<T> TreiberStack<T> {
+    pub fn new() -> Self {
+        Self {
+            head: Atomic::null(),
+        }
+    }
+
+    pub fn push(&self, t: T) {
+        let guard = epoch::pin();
+        let mut node = Owned::new(Node {
+            data: ManuallyDrop::new(t),
+            next: Atomic::null(),
+        });
+
+        loop {
+            let head = self.head.load(Ordering::Acquire, &guard);
+            node.next.store(head, Ordering::Relaxed);
+
+            match self
+                .head
+                .compare_exchange(head, node, Ordering::Release, Ordering::Relaxed, &guard)
+            {
+                Ok(_) => return,
+                Err(e) => node = e.new,
+            }
+        }
+    }
+
+    pub fn pop(&self) -> Option<T> {
+        let guard = epoch::pin();
+
+        loop {
+            let head = self.head.load(Ordering::Acquire, &guard);
+            let head_ref = unsafe { head.as_ref() }?;
+            let next = head_ref.next.load(Ordering::Acquire, &guard);
+
+            if self
+                .head
+                .compare_exchange(head, next, Ordering::Release, Ordering::Relaxed, &guard)
+                .is_ok()
+            {
+                // Safety: this thread just unlinked `head`, so it is the sole owner of the data
+                // it holds; the node itself is only freed once the epoch guarantees no other
+                // thread can still be dereferencing it.
+                unsafe {
+                    let data = ptr::read(&head_ref.data);
+                    guard.defer_destroy(head);
+                    return Some(ManuallyDrop::into_inner(data));
+                }
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        let guard = epoch::pin();
+        let mut count = 0;
+        let mut current = self.head.load(Ordering::Acquire, &guard);
+
+        while let Some(node) = unsafe { current.as_ref() } {
+            count += 1;
+            current = node.next.load(Ordering::Acquire, &guard);
+        }
+
+        count
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        let guard = epoch::pin();
+        self.head.load(Ordering::Acquire, &guard).is_null()
+    }
+}
+
+impl<T> Default for TreiberStack<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Drop for TreiberStack<T> {
+    fn drop(&mut self) {
+        // No other thread can observe `self` anymore, so this can run outside any epoch guard.
+        let mut current = self.head.load(Ordering::Relaxed, unsafe { epoch::unprotected() });
+
+        while let Some(node) = unsafe { current.as_ref() } {
+            let next = node.next.load(Ordering::Relaxed, unsafe { epoch::unprotected() });
+            // Safety: `self` is being dropped, so this is the only reference left to `current`.
+            unsafe {
+                ManuallyDrop::drop(&mut (*current.as_raw().cast_mut()).data);
+                drop(current.into_owned());
+            }
+            current = next;
+        }
+    }
+}